@@ -47,6 +47,10 @@ pub trait Application: Send + Clone + 'static {
     }
 
     /// Check the given transaction before putting it into the local mempool.
+    ///
+    /// This is also called to recheck transactions remaining in the mempool
+    /// after each block is committed, which applications can detect using
+    /// [`RequestCheckTx::is_recheck`].
     fn check_tx(&self, _request: RequestCheckTx) -> ResponseCheckTx {
         Default::default()
     }
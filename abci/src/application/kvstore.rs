@@ -5,14 +5,15 @@ use std::{
     sync::mpsc::{channel, Receiver, Sender},
 };
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use tendermint_proto::abci::{
-    Event, EventAttribute, RequestCheckTx, RequestDeliverTx, RequestInfo, RequestQuery,
-    ResponseCheckTx, ResponseCommit, ResponseDeliverTx, ResponseInfo, ResponseQuery,
+    Event, RequestBeginBlock, RequestCheckTx, RequestDeliverTx, RequestInfo, RequestInitChain,
+    RequestQuery, ResponseBeginBlock, ResponseCheckTx, ResponseCommit, ResponseDeliverTx,
+    ResponseInfo, ResponseInitChain, ResponseQuery,
 };
-use tracing::{debug, info};
+use tracing::debug;
 
-use crate::{codec::MAX_VARINT_LENGTH, Application, Error};
+use crate::{codec::MAX_VARINT_LENGTH, Application, Error, Handshake};
 
 /// In-memory, hashmap-backed key/value store ABCI application.
 ///
@@ -66,16 +67,22 @@ use crate::{codec::MAX_VARINT_LENGTH, Application, Error};
 #[derive(Debug, Clone)]
 pub struct KeyValueStoreApp {
     cmd_tx: Sender<Command>,
+    handshake: Handshake,
 }
 
 impl KeyValueStoreApp {
     /// Constructor.
     pub fn new() -> (Self, KeyValueStoreDriver) {
         let (cmd_tx, cmd_rx) = channel();
-        (Self { cmd_tx }, KeyValueStoreDriver::new(cmd_rx))
+        let handshake = Handshake::new("kvstore-rs", "0.1.0", 1)
+            .with_last_block(0, vec![0_u8; MAX_VARINT_LENGTH])
+            .expect("height 0 is valid");
+        (Self { cmd_tx, handshake }, KeyValueStoreDriver::new(cmd_rx))
     }
 
     /// Attempt to retrieve the value associated with the given key.
+    ///
+    /// Also returns the height of the last committed block.
     pub fn get<K: AsRef<str>>(&self, key: K) -> Result<(i64, Option<String>), Error> {
         let (result_tx, result_rx) = channel();
         channel_send(
@@ -85,7 +92,8 @@ impl KeyValueStoreApp {
                 result_tx,
             },
         )?;
-        channel_recv(&result_rx)
+        let value = channel_recv(&result_rx)?;
+        Ok((self.handshake.last_block().0, value))
     }
 
     /// Attempt to set the value associated with the given key.
@@ -112,22 +120,14 @@ impl KeyValueStoreApp {
 
 impl Application for KeyValueStoreApp {
     fn info(&self, request: RequestInfo) -> ResponseInfo {
-        debug!(
-            "Got info request. Tendermint version: {}; Block version: {}; P2P version: {}",
-            request.version, request.block_version, request.p2p_version
-        );
-
-        let (result_tx, result_rx) = channel();
-        channel_send(&self.cmd_tx, Command::GetInfo { result_tx }).unwrap();
-        let (last_block_height, last_block_app_hash) = channel_recv(&result_rx).unwrap();
+        self.handshake.info(&request)
+    }
 
-        ResponseInfo {
-            data: "kvstore-rs".to_string(),
-            version: "0.1.0".to_string(),
-            app_version: 1,
-            last_block_height,
-            last_block_app_hash: last_block_app_hash.into(),
+    fn init_chain(&self, request: RequestInitChain) -> ResponseInitChain {
+        if let Err(e) = self.handshake.init_chain(&request) {
+            panic!("Failed to initialize chain: {e}");
         }
+        Default::default()
     }
 
     fn query(&self, request: RequestQuery) -> ResponseQuery {
@@ -165,18 +165,17 @@ impl Application for KeyValueStoreApp {
         }
     }
 
-    fn check_tx(&self, _request: RequestCheckTx) -> ResponseCheckTx {
-        ResponseCheckTx {
-            code: 0,
-            data: Default::default(),
-            log: "".to_string(),
-            info: "".to_string(),
-            gas_wanted: 1,
-            gas_used: 0,
-            events: vec![],
-            codespace: "".to_string(),
-            ..Default::default()
+    fn begin_block(&self, request: RequestBeginBlock) -> ResponseBeginBlock {
+        if let Some(header) = &request.header {
+            if let Err(e) = self.handshake.begin_block(header.height) {
+                panic!("Cannot execute block: {e}");
+            }
         }
+        Default::default()
+    }
+
+    fn check_tx(&self, _request: RequestCheckTx) -> ResponseCheckTx {
+        ResponseCheckTx::success(Bytes::new(), vec![]).with_gas(1, 0)
     }
 
     fn deliver_tx(&self, request: RequestDeliverTx) -> ResponseDeliverTx {
@@ -188,44 +187,22 @@ impl Application for KeyValueStoreApp {
             (tx, tx)
         };
         let _ = self.set(key, value).unwrap();
-        ResponseDeliverTx {
-            code: 0,
-            data: Default::default(),
-            log: "".to_string(),
-            info: "".to_string(),
-            gas_wanted: 0,
-            gas_used: 0,
-            events: vec![Event {
-                r#type: "app".to_string(),
-                attributes: vec![
-                    EventAttribute {
-                        key: "key".to_string().into_bytes().into(),
-                        value: key.to_string().into_bytes().into(),
-                        index: true,
-                    },
-                    EventAttribute {
-                        key: "index_key".to_string().into_bytes().into(),
-                        value: "index is working".to_string().into_bytes().into(),
-                        index: true,
-                    },
-                    EventAttribute {
-                        key: "noindex_key".to_string().into_bytes().into(),
-                        value: "index is working".to_string().into_bytes().into(),
-                        index: false,
-                    },
-                ],
-            }],
-            codespace: "".to_string(),
-        }
+        ResponseDeliverTx::success(
+            Bytes::new(),
+            vec![Event::new("app")
+                .attr_indexed("key", key)
+                .attr_indexed("index_key", "index is working")
+                .attr("noindex_key", "index is working")],
+        )
     }
 
     fn commit(&self) -> ResponseCommit {
         let (result_tx, result_rx) = channel();
         channel_send(&self.cmd_tx, Command::Commit { result_tx }).unwrap();
-        let (height, app_hash) = channel_recv(&result_rx).unwrap();
-        info!("Committed height {}", height);
+        let app_hash = channel_recv(&result_rx).unwrap();
+        let height = self.handshake.commit(app_hash.clone());
         ResponseCommit {
-            data: app_hash.into(),
+            data: app_hash,
             retain_height: height - 1,
         }
     }
@@ -235,8 +212,6 @@ impl Application for KeyValueStoreApp {
 #[derive(Debug)]
 pub struct KeyValueStoreDriver {
     store: HashMap<String, String>,
-    height: i64,
-    app_hash: Vec<u8>,
     cmd_rx: Receiver<Command>,
 }
 
@@ -244,8 +219,6 @@ impl KeyValueStoreDriver {
     fn new(cmd_rx: Receiver<Command>) -> Self {
         Self {
             store: HashMap::new(),
-            height: 0,
-            app_hash: vec![0_u8; MAX_VARINT_LENGTH],
             cmd_rx,
         }
     }
@@ -255,15 +228,9 @@ impl KeyValueStoreDriver {
         loop {
             let cmd = self.cmd_rx.recv().map_err(Error::channel_recv)?;
             match cmd {
-                Command::GetInfo { result_tx } => {
-                    channel_send(&result_tx, (self.height, self.app_hash.clone()))?
-                },
                 Command::Get { key, result_tx } => {
                     debug!("Getting value for \"{}\"", key);
-                    channel_send(
-                        &result_tx,
-                        (self.height, self.store.get(&key).map(Clone::clone)),
-                    )?;
+                    channel_send(&result_tx, self.store.get(&key).cloned())?;
                 },
                 Command::Set {
                     key,
@@ -278,25 +245,21 @@ impl KeyValueStoreDriver {
         }
     }
 
-    fn commit(&mut self, result_tx: Sender<(i64, Vec<u8>)>) -> Result<(), Error> {
+    fn commit(&mut self, result_tx: Sender<Bytes>) -> Result<(), Error> {
         // As in the Go-based key/value store, simply encode the number of
         // items as the "app hash"
         let mut app_hash = BytesMut::with_capacity(MAX_VARINT_LENGTH);
         prost::encoding::encode_varint(self.store.len() as u64, &mut app_hash);
-        self.app_hash = app_hash.to_vec();
-        self.height += 1;
-        channel_send(&result_tx, (self.height, self.app_hash.clone()))
+        channel_send(&result_tx, app_hash.freeze())
     }
 }
 
 #[derive(Debug, Clone)]
 enum Command {
-    /// Get the height of the last commit.
-    GetInfo { result_tx: Sender<(i64, Vec<u8>)> },
     /// Get the key associated with `key`.
     Get {
         key: String,
-        result_tx: Sender<(i64, Option<String>)>,
+        result_tx: Sender<Option<String>>,
     },
     /// Set the value of `key` to to `value`.
     Set {
@@ -306,7 +269,7 @@ enum Command {
     },
     /// Commit the current state of the application, which involves recomputing
     /// the application's hash.
-    Commit { result_tx: Sender<(i64, Vec<u8>)> },
+    Commit { result_tx: Sender<Bytes> },
 }
 
 fn channel_send<T>(tx: &Sender<T>, value: T) -> Result<(), Error> {
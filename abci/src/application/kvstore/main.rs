@@ -1,5 +1,7 @@
 //! In-memory key/value store application for Tendermint.
 
+use std::path::PathBuf;
+
 use structopt::StructOpt;
 use tendermint_abci::{KeyValueStoreApp, ServerBuilder};
 use tracing_subscriber::filter::LevelFilter;
@@ -19,6 +21,14 @@ struct Opt {
     #[structopt(short, long, default_value = "1048576")]
     read_buf_size: usize,
 
+    /// The maximum size, in bytes, of any single request or response.
+    #[structopt(short, long, default_value = "104857600")]
+    max_message_size: usize,
+
+    /// Record all ABCI requests and responses to this file.
+    #[structopt(long)]
+    record: Option<PathBuf>,
+
     /// Increase output logging verbosity to DEBUG level.
     #[structopt(short, long)]
     verbose: bool,
@@ -40,7 +50,11 @@ fn main() {
     tracing_subscriber::fmt().with_max_level(log_level).init();
 
     let (app, driver) = KeyValueStoreApp::new();
-    let server = ServerBuilder::new(opt.read_buf_size)
+    let mut builder = ServerBuilder::new(opt.read_buf_size).max_message_size(opt.max_message_size);
+    if let Some(path) = opt.record {
+        builder = builder.record(path);
+    }
+    let server = builder
         .bind(format!("{}:{}", opt.host, opt.port), app)
         .unwrap();
     std::thread::spawn(move || driver.run());
@@ -12,7 +12,10 @@ use tendermint_proto::abci::{
     ResponseLoadSnapshotChunk, ResponseOfferSnapshot, ResponseQuery, ResponseSetOption,
 };
 
-use crate::{codec::ClientCodec, Error};
+use crate::{
+    codec::{ClientCodec, DEFAULT_MAX_MESSAGE_SIZE},
+    Error,
+};
 
 /// The size of the read buffer for the client in its receiving of responses
 /// from the server.
@@ -21,12 +24,23 @@ pub const DEFAULT_CLIENT_READ_BUF_SIZE: usize = 1024;
 /// Builder for a blocking ABCI client.
 pub struct ClientBuilder {
     read_buf_size: usize,
+    max_message_size: usize,
 }
 
 impl ClientBuilder {
     /// Builder constructor.
     pub fn new(read_buf_size: usize) -> Self {
-        Self { read_buf_size }
+        Self {
+            read_buf_size,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// Set the maximum size, in bytes, of any single request sent or response
+    /// received by the client.
+    pub fn max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
     }
 
     /// Client constructor that attempts to connect to the given network
@@ -34,16 +48,14 @@ impl ClientBuilder {
     pub fn connect<A: ToSocketAddrs>(self, addr: A) -> Result<Client, Error> {
         let stream = TcpStream::connect(addr).map_err(Error::io)?;
         Ok(Client {
-            codec: ClientCodec::new(stream, self.read_buf_size),
+            codec: ClientCodec::new(stream, self.read_buf_size, self.max_message_size),
         })
     }
 }
 
 impl Default for ClientBuilder {
     fn default() -> Self {
-        Self {
-            read_buf_size: DEFAULT_CLIENT_READ_BUF_SIZE,
-        }
+        Self::new(DEFAULT_CLIENT_READ_BUF_SIZE)
     }
 }
 
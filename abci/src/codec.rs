@@ -19,6 +19,14 @@ use crate::error::Error;
 /// we're encountering a decoding error for a varint.
 pub const MAX_VARINT_LENGTH: usize = 16;
 
+/// The default maximum size of a single ABCI message, in bytes (100MB). This
+/// matches the maximum block size allowed by Tendermint.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 100 * 1024 * 1024;
+
+/// The maximum capacity of the write buffer that is retained between sent
+/// messages (1MB).
+const MAX_RETAINED_WRITE_BUF_SIZE: usize = 1024 * 1024;
+
 /// The server receives incoming requests, and sends outgoing responses.
 pub type ServerCodec<S> = Codec<S, Request, Response>;
 
@@ -30,32 +38,40 @@ pub type ClientCodec<S> = Codec<S, Response, Request>;
 /// sending instances of `O`.
 pub struct Codec<S, I, O> {
     stream: S,
-    // Long-running read buffer
+    // Long-running read buffer, grown as needed to hold incoming messages
     read_buf: BytesMut,
     // Fixed-length read window
     read_window: Vec<u8>,
+    // Maximum size of a single incoming or outgoing message
+    max_message_size: usize,
     write_buf: BytesMut,
     _incoming: PhantomData<I>,
     _outgoing: PhantomData<O>,
 }
 
-impl<S, I, O> Codec<S, I, O>
-where
-    S: Read + Write,
-    I: Message + Default,
-    O: Message,
-{
+impl<S, I, O> Codec<S, I, O> {
     /// Constructor.
-    pub fn new(stream: S, read_buf_size: usize) -> Self {
+    ///
+    /// Incoming data is read from the stream in chunks of up to
+    /// `read_buf_size` bytes. Messages (in either direction) whose encoded
+    /// length exceeds `max_message_size` are rejected.
+    pub fn new(stream: S, read_buf_size: usize, max_message_size: usize) -> Self {
         Self {
             stream,
             read_buf: BytesMut::new(),
             read_window: vec![0_u8; read_buf_size],
+            max_message_size,
             write_buf: BytesMut::new(),
             _incoming: Default::default(),
             _outgoing: Default::default(),
         }
     }
+
+    /// Set the maximum size, in bytes, of messages in either direction.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
 }
 
 // Iterating over a codec produces instances of `Result<I>`.
@@ -69,7 +85,7 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             // Try to decode an incoming message from our buffer first
-            match decode_length_delimited::<I>(&mut self.read_buf) {
+            match decode_length_delimited::<I>(&mut self.read_buf, self.max_message_size) {
                 Ok(Some(incoming)) => return Some(Ok(incoming)),
                 Err(e) => return Some(Err(e)),
                 _ => (), // not enough data to decode a message, let's continue.
@@ -98,6 +114,13 @@ where
 {
     /// Send a message using this codec.
     pub fn send(&mut self, message: O) -> Result<(), Error> {
+        let encoded_len = message.encoded_len();
+        if encoded_len > self.max_message_size {
+            return Err(Error::message_too_long(
+                encoded_len as u64,
+                self.max_message_size,
+            ));
+        }
         encode_length_delimited(message, &mut self.write_buf)?;
         while !self.write_buf.is_empty() {
             let bytes_written = self
@@ -116,53 +139,66 @@ where
 
         self.stream.flush().map_err(Error::io)?;
 
+        // Don't hold on to the memory used to send exceptionally large
+        // messages (e.g. snapshot chunks) for the lifetime of the connection.
+        if self.write_buf.capacity() > MAX_RETAINED_WRITE_BUF_SIZE {
+            self.write_buf = BytesMut::new();
+        }
+
         Ok(())
     }
 }
 
 /// Encode the given message with a length prefix.
+///
+/// The message is encoded directly into the destination buffer, without any
+/// intermediate copies.
 pub fn encode_length_delimited<M, B>(message: M, mut dst: &mut B) -> Result<(), Error>
 where
     M: Message,
     B: BufMut,
 {
-    let mut buf = BytesMut::new();
-    message.encode(&mut buf).map_err(Error::encode)?;
-
-    let buf = buf.freeze();
-    encode_varint(buf.len() as u64, &mut dst);
-    dst.put(buf);
-    Ok(())
+    encode_varint(message.encoded_len() as u64, &mut dst);
+    message.encode(dst).map_err(Error::encode)
 }
 
 /// Attempt to decode a message of type `M` from the given source buffer.
-pub fn decode_length_delimited<M>(src: &mut BytesMut) -> Result<Option<M>, Error>
+///
+/// Fails as soon as the length prefix indicates a message longer than
+/// `max_message_size`, without waiting for the rest of the message to arrive.
+pub fn decode_length_delimited<M>(
+    src: &mut BytesMut,
+    max_message_size: usize,
+) -> Result<Option<M>, Error>
 where
     M: Message + Default,
 {
-    let src_len = src.len();
-    let mut tmp = src.clone().freeze();
-    let encoded_len = match decode_varint(&mut tmp) {
+    // Only peek at the length delimiter until we know that the entire
+    // message is available, so as to avoid copying the (potentially large)
+    // buffered data on every attempt.
+    let mut peek = &src[..];
+    let encoded_len = match decode_varint(&mut peek) {
         Ok(len) => len,
         // We've potentially only received a partial length delimiter
-        Err(_) if src_len <= MAX_VARINT_LENGTH => return Ok(None),
+        Err(_) if src.len() <= MAX_VARINT_LENGTH => return Ok(None),
         Err(e) => return Err(e),
     };
-    let remaining = tmp.remaining() as u64;
-    if remaining < encoded_len {
+    if encoded_len > max_message_size as u64 {
+        return Err(Error::message_too_long(encoded_len, max_message_size));
+    }
+    if (peek.remaining() as u64) < encoded_len {
         // We don't have enough data yet to decode the entire message
-        Ok(None)
-    } else {
-        let delim_len = src_len - tmp.remaining();
-        // We only advance the source buffer once we're sure we have enough
-        // data to try to decode the result.
-        src.advance(delim_len + (encoded_len as usize));
-
-        let mut result_bytes = BytesMut::from(tmp.split_to(encoded_len as usize).as_ref());
-        let res = M::decode(&mut result_bytes).map_err(Error::decode)?;
-
-        Ok(Some(res))
+        return Ok(None);
     }
+
+    // Decode from a borrowed slice, so that `Bytes` fields (e.g.
+    // transactions) are copied out of the read buffer rather than keeping it
+    // alive for as long as the application holds on to them.
+    let encoded_len = encoded_len as usize;
+    let result = M::decode(&peek[..encoded_len]).map_err(Error::decode);
+    let delim_len = src.len() - peek.remaining();
+    src.advance(delim_len + encoded_len);
+    result.map(Some)
 }
 
 // encode_varint and decode_varint will be removed once
@@ -17,6 +17,16 @@ define_error! {
             [ DisplayError<prost::DecodeError> ]
             | _ | { "error encoding protocol buffer" },
 
+        MessageTooLong
+            {
+                length: u64,
+                max: usize,
+            }
+            | e | {
+                format_args!("message of {0} bytes exceeds the maximum allowed size of {1} bytes",
+                    e.length, e.max)
+            },
+
         ServerConnectionTerminated
             | _ | { "server connection terminated" },
 
@@ -33,6 +43,32 @@ define_error! {
                     e.expected, e.got)
             },
 
+        UnexpectedBlockHeight
+            {
+                expected: i64,
+                got: i64,
+            }
+            | e | {
+                format_args!("unexpected block height: expected {0}, but got {1}",
+                    e.expected, e.got)
+            },
+
+        InvalidBlockHeight
+            { height: i64 }
+            | e | {
+                format_args!("invalid block height: {0}", e.height)
+            },
+
+        ChainAlreadyInitialized
+            { last_block_height: i64 }
+            | e | {
+                format_args!("chain already initialized: last block height is {0}",
+                    e.last_block_height)
+            },
+
+        RecorderPoisoned
+            | _ | { "request recorder lock poisoned" },
+
         ChannelSend
             | _ | { "channel send error" },
 
@@ -0,0 +1,162 @@
+//! Bookkeeping for the Tendermint handshake.
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use bytes::Bytes;
+use tendermint_proto::abci::{RequestInfo, RequestInitChain, ResponseInfo};
+use tracing::{debug, info};
+
+use crate::Error;
+
+/// Tracks the last block committed by an application, and uses it to answer
+/// the `Info` requests with which Tendermint starts its handshake.
+///
+/// On startup, Tendermint replays any blocks that it has stored but that the
+/// application has not yet committed, starting from the
+/// `last_block_height` reported by the application. It is therefore critical
+/// that the application reports the height and app hash of the last block it
+/// actually persisted. Applications that persist their state should restore
+/// these values using [`Handshake::with_last_block`].
+///
+/// A `Handshake` is cheaply cloneable, and all clones share the same state,
+/// so it can be stored directly within an [`Application`].
+///
+/// ## Example
+/// ```rust
+/// use tendermint_abci::Handshake;
+/// use tendermint_proto::abci::RequestInfo;
+///
+/// let handshake = Handshake::new("my-app", "0.1.0", 1);
+/// assert_eq!(handshake.info(&RequestInfo::default()).last_block_height, 0);
+///
+/// handshake.begin_block(1).unwrap();
+/// assert_eq!(handshake.commit("app-hash"), 1);
+/// // Tendermint must not skip blocks or send them more than once
+/// assert!(handshake.begin_block(1).is_err());
+/// assert!(handshake.begin_block(3).is_err());
+/// ```
+///
+/// [`Application`]: crate::Application
+#[derive(Debug, Clone)]
+pub struct Handshake {
+    data: String,
+    version: String,
+    app_version: u64,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    last_block_height: i64,
+    last_block_app_hash: Bytes,
+    next_block_height: i64,
+}
+
+impl Handshake {
+    /// Constructor for an application that has not yet committed any blocks.
+    ///
+    /// `data` and `version` are arbitrary, and are reported to Tendermint
+    /// alongside the application protocol version `app_version`.
+    pub fn new<D, V>(data: D, version: V, app_version: u64) -> Self
+    where
+        D: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            data: data.into(),
+            version: version.into(),
+            app_version,
+            state: Arc::new(Mutex::new(State {
+                last_block_height: 0,
+                last_block_app_hash: Bytes::new(),
+                next_block_height: 1,
+            })),
+        }
+    }
+
+    /// Restore the height and app hash of the last block committed by the
+    /// application (e.g. from its persistent storage).
+    ///
+    /// Fails if the height is negative, or if it is the maximum possible
+    /// height (such that there can be no next block).
+    pub fn with_last_block<H: Into<Bytes>>(self, height: i64, app_hash: H) -> Result<Self, Error> {
+        if height < 0 || height == i64::MAX {
+            return Err(Error::invalid_block_height(height));
+        }
+        {
+            let mut state = self.state();
+            state.last_block_height = height;
+            state.last_block_app_hash = app_hash.into();
+            state.next_block_height = height + 1;
+        }
+        Ok(self)
+    }
+
+    /// The height and app hash of the last block committed by the
+    /// application.
+    pub fn last_block(&self) -> (i64, Bytes) {
+        let state = self.state();
+        (state.last_block_height, state.last_block_app_hash.clone())
+    }
+
+    /// Produce the response to the given `Info` request.
+    pub fn info(&self, request: &RequestInfo) -> ResponseInfo {
+        debug!(
+            "Got info request. Tendermint version: {}; Block version: {}; P2P version: {}",
+            request.version, request.block_version, request.p2p_version
+        );
+        let (last_block_height, last_block_app_hash) = self.last_block();
+        ResponseInfo {
+            data: self.data.clone(),
+            version: self.version.clone(),
+            app_version: self.app_version,
+            last_block_height,
+            last_block_app_hash,
+        }
+    }
+
+    /// Record the given `InitChain` request, which Tendermint only sends if
+    /// the application reported that it has not yet committed any blocks.
+    pub fn init_chain(&self, request: &RequestInitChain) -> Result<(), Error> {
+        let mut state = self.state();
+        if state.last_block_height != 0 {
+            return Err(Error::chain_already_initialized(state.last_block_height));
+        }
+        state.next_block_height = request.initial_height.max(1);
+        Ok(())
+    }
+
+    /// Check that the block at the given height is the one the application
+    /// expects to execute next.
+    ///
+    /// Fails if Tendermint attempts to skip blocks or to execute an
+    /// already-committed block, which indicates that the height reported
+    /// during the handshake does not match the application's actual state.
+    pub fn begin_block(&self, height: i64) -> Result<(), Error> {
+        let state = self.state();
+        if height != state.next_block_height {
+            return Err(Error::unexpected_block_height(
+                state.next_block_height,
+                height,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Record that the application committed the current block with the
+    /// given app hash, returning the height of the committed block.
+    pub fn commit<H: Into<Bytes>>(&self, app_hash: H) -> i64 {
+        let mut state = self.state();
+        state.last_block_height = state.next_block_height;
+        state.last_block_app_hash = app_hash.into();
+        state.next_block_height += 1;
+        info!("Committed height {}", state.last_block_height);
+        state.last_block_height
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // The state is always left consistent, so it is safe to keep using it
+        // even if another thread panicked while holding the lock.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
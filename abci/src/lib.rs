@@ -7,7 +7,11 @@ mod application;
 mod client;
 mod codec;
 pub mod error;
+mod handshake;
+pub mod recording;
 mod server;
+#[cfg(feature = "snapshots")]
+pub mod snapshots;
 
 // Common exports
 // Example applications
@@ -19,4 +23,5 @@ pub use application::Application;
 #[cfg(feature = "client")]
 pub use client::{Client, ClientBuilder};
 pub use error::Error;
+pub use handshake::Handshake;
 pub use server::{Server, ServerBuilder};
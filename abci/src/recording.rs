@@ -0,0 +1,184 @@
+//! Recording and replaying of ABCI request/response exchanges.
+//!
+//! A [`Server`] can be configured (via [`ServerBuilder::record`]) to append
+//! every request it handles, together with the application's response and
+//! the time taken to produce it, to a file. Such recordings can subsequently
+//! be read using a [`RecordReader`] and [`replay`]ed against an application
+//! in order to reproduce, for example, a consensus failure.
+//!
+//! Recordings consist of length-delimited, Protobuf-encoded [`Record`]s.
+//!
+//! [`Server`]: crate::Server
+//! [`ServerBuilder::record`]: crate::ServerBuilder::record
+
+use std::{fs::File, io::Read, path::Path, time::Duration};
+
+use tendermint_proto::abci::{Request, Response};
+
+use crate::{
+    application::RequestDispatcher,
+    codec::{Codec, DEFAULT_MAX_MESSAGE_SIZE, MAX_VARINT_LENGTH},
+    Application, Error,
+};
+
+/// The default maximum size of a single record, which contains both a request
+/// and a response.
+pub const DEFAULT_MAX_RECORD_SIZE: usize = max_record_size(DEFAULT_MAX_MESSAGE_SIZE);
+
+/// The size of the read buffer used when reading recordings (64KB).
+const RECORD_READ_BUF_SIZE: usize = 64 * 1024;
+
+/// The maximum length of the client address stored in a record.
+const MAX_RECORD_CLIENT_LENGTH: usize = 64;
+
+/// An upper bound on the size of a record's fields other than the request and
+/// response themselves (i.e. the tags and lengths of all fields, the client
+/// address and the duration).
+const RECORD_OVERHEAD: usize = 4 * (1 + MAX_VARINT_LENGTH) + MAX_RECORD_CLIENT_LENGTH;
+
+/// Writes records to an underlying file.
+pub(crate) type RecordWriter = Codec<File, Record, Record>;
+
+/// Construct a writer for records of requests and responses of up to
+/// `max_message_size` bytes each.
+pub(crate) fn record_writer(file: File, max_message_size: usize) -> RecordWriter {
+    // Records are never read back through the writer, so it needs no read
+    // buffer
+    Codec::new(file, 0, max_record_size(max_message_size))
+}
+
+/// The maximum size of a record of requests and responses of up to
+/// `max_message_size` bytes each.
+pub const fn max_record_size(max_message_size: usize) -> usize {
+    max_message_size
+        .saturating_mul(2)
+        .saturating_add(RECORD_OVERHEAD)
+}
+
+/// A single recorded ABCI request, along with the application's response.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Record {
+    /// The address of the client from which the request was received.
+    #[prost(string, tag = "1")]
+    pub client: String,
+    /// The request received from the client.
+    #[prost(message, optional, tag = "2")]
+    pub request: Option<Request>,
+    /// The response produced by the application, or `None` if the server
+    /// closed the connection instead of responding. This happens if the
+    /// application panicked while executing a block, or if its response
+    /// exceeded the maximum message size.
+    #[prost(message, optional, tag = "3")]
+    pub response: Option<Response>,
+    /// The time taken by the application to produce the response, in
+    /// nanoseconds.
+    #[prost(uint64, tag = "4")]
+    pub duration_nanos: u64,
+}
+
+impl Record {
+    /// Constructor, truncating the client address if necessary so that the
+    /// record's size stays within [`max_record_size`].
+    pub(crate) fn new(
+        client: &str,
+        request: Option<Request>,
+        response: Option<Response>,
+        duration: Duration,
+    ) -> Self {
+        let mut client_len = client.len().min(MAX_RECORD_CLIENT_LENGTH);
+        while !client.is_char_boundary(client_len) {
+            client_len -= 1;
+        }
+        Self {
+            client: client[..client_len].to_string(),
+            request,
+            response,
+            duration_nanos: duration.as_nanos() as u64,
+        }
+    }
+}
+
+/// Reads [`Record`]s from a recording.
+pub struct RecordReader<R> {
+    codec: Codec<R, Record, Record>,
+}
+
+impl RecordReader<File> {
+    /// Open the recording at the given path for reading.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(Self::new(File::open(path).map_err(Error::io)?))
+    }
+}
+
+impl<R: Read> RecordReader<R> {
+    /// Constructor.
+    ///
+    /// Records larger than [`DEFAULT_MAX_RECORD_SIZE`] are rejected. Use
+    /// [`RecordReader::with_max_record_size`] to read recordings made by a
+    /// server configured with a larger [`ServerBuilder::max_message_size`].
+    ///
+    /// [`ServerBuilder::max_message_size`]: crate::ServerBuilder::max_message_size
+    pub fn new(reader: R) -> Self {
+        Self {
+            codec: Codec::new(reader, RECORD_READ_BUF_SIZE, DEFAULT_MAX_RECORD_SIZE),
+        }
+    }
+
+    /// Set the maximum size, in bytes, of a single record.
+    ///
+    /// A server configured with a maximum message size of `n` bytes produces
+    /// records of up to [`max_record_size`]`(n)` bytes.
+    pub fn with_max_record_size(mut self, max_record_size: usize) -> Self {
+        self.codec = self.codec.with_max_message_size(max_record_size);
+        self
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = Result<Record, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.codec.next()
+    }
+}
+
+/// A recorded request for which replaying produced a different response.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// The position of the record in the recording, starting from 0.
+    pub index: usize,
+    /// The original record.
+    pub record: Record,
+    /// The response produced by the application during replay.
+    pub response: Response,
+}
+
+/// Replay all requests in the given recording against the given application,
+/// in order.
+///
+/// Returns the records for which the application produced a response that
+/// differs from the recorded one. Requests during which the application
+/// originally panicked are replayed as well, in order to reproduce the panic.
+pub fn replay<App, R>(app: &App, records: RecordReader<R>) -> Result<Vec<Divergence>, Error>
+where
+    App: Application,
+    R: Read,
+{
+    let mut divergences = Vec::new();
+    for (index, record) in records.enumerate() {
+        let record = record?;
+        let request = match &record.request {
+            Some(request) => request.clone(),
+            None => continue,
+        };
+        let response = app.handle(request);
+        if record.response.as_ref() != Some(&response) {
+            divergences.push(Divergence {
+                index,
+                record,
+                response,
+            });
+        }
+    }
+    Ok(divergences)
+}
@@ -1,21 +1,45 @@
 //! ABCI application server interface.
 
 use std::{
+    any::Any,
+    fs::OpenOptions,
     net::{TcpListener, TcpStream, ToSocketAddrs},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{Arc, Mutex},
     thread,
+    time::Instant,
 };
 
+use prost::Message;
+use tendermint_proto::abci::{
+    request, response, response_apply_snapshot_chunk, response_offer_snapshot, Request, Response,
+    ResponseApplySnapshotChunk, ResponseCheckTx, ResponseException, ResponseOfferSnapshot,
+    ResponseQuery,
+};
 use tracing::{error, info};
 
-use crate::{application::RequestDispatcher, codec::ServerCodec, error::Error, Application};
+use crate::{
+    application::RequestDispatcher,
+    codec::{ServerCodec, DEFAULT_MAX_MESSAGE_SIZE},
+    error::Error,
+    recording::{self, Record, RecordWriter},
+    Application,
+};
 
 /// The size of the read buffer for each incoming connection to the ABCI
 /// server (1MB).
 pub const DEFAULT_SERVER_READ_BUF_SIZE: usize = 1024 * 1024;
 
+/// The response code returned for `CheckTx` and `Query` requests during which
+/// the application panicked.
+const PANIC_RESPONSE_CODE: u32 = 1;
+
 /// Allows us to configure and construct an ABCI server.
 pub struct ServerBuilder {
     read_buf_size: usize,
+    max_message_size: usize,
+    recording_path: Option<PathBuf>,
 }
 
 impl ServerBuilder {
@@ -25,7 +49,33 @@ impl ServerBuilder {
     /// incoming data from the client. This needs to be tuned for your
     /// application.
     pub fn new(read_buf_size: usize) -> Self {
-        Self { read_buf_size }
+        Self {
+            read_buf_size,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            recording_path: None,
+        }
+    }
+
+    /// Set the maximum size, in bytes, of any single request received or
+    /// response sent by the server.
+    ///
+    /// Connections from clients sending larger requests are closed without
+    /// attempting to buffer the entire request.
+    pub fn max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Record every request handled by the server, along with the
+    /// application's response, to the file at the given path.
+    ///
+    /// Records are appended to the file if it already exists. See the
+    /// [`recording`] module for how to read and replay them.
+    ///
+    /// [`recording`]: crate::recording
+    pub fn record<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.recording_path = Some(path.into());
+        self
     }
 
     /// Constructor for an ABCI server.
@@ -38,6 +88,21 @@ impl ServerBuilder {
         Addr: ToSocketAddrs,
         App: Application,
     {
+        let recorder = match self.recording_path {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map_err(Error::io)?;
+                info!("Recording ABCI requests to {}", path.display());
+                Some(Arc::new(Mutex::new(recording::record_writer(
+                    file,
+                    self.max_message_size,
+                ))))
+            },
+            None => None,
+        };
         let listener = TcpListener::bind(addr).map_err(Error::io)?;
         let local_addr = listener.local_addr().map_err(Error::io)?.to_string();
         info!("ABCI server running at {}", local_addr);
@@ -46,15 +111,15 @@ impl ServerBuilder {
             listener,
             local_addr,
             read_buf_size: self.read_buf_size,
+            max_message_size: self.max_message_size,
+            recorder,
         })
     }
 }
 
 impl Default for ServerBuilder {
     fn default() -> Self {
-        Self {
-            read_buf_size: DEFAULT_SERVER_READ_BUF_SIZE,
-        }
+        Self::new(DEFAULT_SERVER_READ_BUF_SIZE)
     }
 }
 
@@ -64,11 +129,30 @@ impl Default for ServerBuilder {
 /// application is cloned for access in each thread. It is up to the
 /// application developer to manage shared state across these different
 /// threads.
+///
+/// If the application panics while handling a request, the server responds
+/// as follows:
+/// * `CheckTx` and `Query` requests receive a response with a non-zero code,
+///   with the panic message as its log.
+/// * State sync requests receive a response that Tendermint handles without
+///   closing the connection: `ListSnapshots` an empty list of snapshots,
+///   `LoadSnapshotChunk` an empty chunk, `OfferSnapshot` a `REJECT` result
+///   and `ApplySnapshotChunk` an `ABORT` result.
+/// * Other requests (e.g. `Info`) receive an exception response, which
+///   causes Tendermint to close the connection.
+/// * For `InitChain`, `BeginBlock`, `DeliverTx`, `EndBlock` and `Commit`
+///   requests, the panic is logged and the connection closed, as consensus
+///   cannot safely proceed.
+///
+/// Applications sharing state between connections must therefore take care
+/// that a panic does not leave this state inconsistent.
 pub struct Server<App> {
     app: App,
     listener: TcpListener,
     local_addr: String,
     read_buf_size: usize,
+    max_message_size: usize,
+    recorder: Option<Arc<Mutex<RecordWriter>>>,
 }
 
 impl<App: Application> Server<App> {
@@ -90,11 +174,22 @@ impl<App: Application> Server<App> {
     fn spawn_client_handler(&self, stream: TcpStream, addr: String) {
         let app = self.app.clone();
         let read_buf_size = self.read_buf_size;
-        let _ = thread::spawn(move || Self::handle_client(stream, addr, app, read_buf_size));
+        let max_message_size = self.max_message_size;
+        let recorder = self.recorder.clone();
+        let _ = thread::spawn(move || {
+            Self::handle_client(stream, addr, app, read_buf_size, max_message_size, recorder)
+        });
     }
 
-    fn handle_client(stream: TcpStream, addr: String, app: App, read_buf_size: usize) {
-        let mut codec = ServerCodec::new(stream, read_buf_size);
+    fn handle_client(
+        stream: TcpStream,
+        addr: String,
+        app: App,
+        read_buf_size: usize,
+        max_message_size: usize,
+        recorder: Option<Arc<Mutex<RecordWriter>>>,
+    ) {
+        let mut codec = ServerCodec::new(stream, read_buf_size, max_message_size);
         info!("Listening for incoming requests from {}", addr);
         loop {
             let request = match codec.next() {
@@ -113,7 +208,66 @@ impl<App: Application> Server<App> {
                     return;
                 },
             };
-            let response = app.handle(request);
+            let recorded_request = recorder.as_ref().map(|_| request.clone());
+            let kind = RequestKind::of(&request);
+            let started = Instant::now();
+            let response = match panic::catch_unwind(AssertUnwindSafe(|| app.handle(request))) {
+                Ok(response) => Some(response),
+                Err(payload) => {
+                    let message = panic_message(payload.as_ref());
+                    match kind.panic_response(message) {
+                        Some(response) => {
+                            error!(
+                                "Application panicked while handling {} request from client {}: {}",
+                                kind.name(),
+                                addr,
+                                message
+                            );
+                            Some(response)
+                        },
+                        None => {
+                            error!(
+                                "Application panicked while handling {} request from client {}, \
+                                closing connection: {}",
+                                kind.name(),
+                                addr,
+                                message
+                            );
+                            None
+                        },
+                    }
+                },
+            };
+            // Responses exceeding the maximum message size cannot be sent
+            let response = response.filter(|response| {
+                let length = response.encoded_len();
+                if length > max_message_size {
+                    error!(
+                        "Application produced a response of {} bytes for client {}, exceeding \
+                        the maximum message size of {} bytes, closing connection",
+                        length, addr, max_message_size
+                    );
+                }
+                length <= max_message_size
+            });
+            if let Some(recorder) = &recorder {
+                let record =
+                    Record::new(&addr, recorded_request, response.clone(), started.elapsed());
+                let result = match recorder.lock() {
+                    Ok(mut writer) => writer.send(record),
+                    Err(_) => Err(Error::recorder_poisoned()),
+                };
+                if let Err(e) = result {
+                    error!("Failed to record request from client {}: {:?}", addr, e);
+                }
+            }
+            // Close the connection if the application panicked while
+            // executing a block or produced an oversized response, having
+            // recorded the offending request
+            let response = match response {
+                Some(response) => response,
+                None => return,
+            };
             if let Err(e) = codec.send(response) {
                 error!("Failed sending response to client {}: {:?}", addr, e);
                 return;
@@ -121,3 +275,96 @@ impl<App: Application> Server<App> {
         }
     }
 }
+
+/// The kind of an incoming request, which determines how a panic while
+/// handling it is reported to the client.
+#[derive(Debug, Clone, Copy)]
+enum RequestKind {
+    CheckTx,
+    Query,
+    ListSnapshots,
+    OfferSnapshot,
+    LoadSnapshotChunk,
+    ApplySnapshotChunk,
+    /// Requests executing a block, which Tendermint relies on to succeed.
+    Consensus(&'static str),
+    Other(&'static str),
+}
+
+impl RequestKind {
+    fn of(request: &Request) -> Self {
+        use request::Value;
+
+        match &request.value {
+            Some(Value::CheckTx(_)) => Self::CheckTx,
+            Some(Value::Query(_)) => Self::Query,
+            Some(Value::InitChain(_)) => Self::Consensus("InitChain"),
+            Some(Value::BeginBlock(_)) => Self::Consensus("BeginBlock"),
+            Some(Value::DeliverTx(_)) => Self::Consensus("DeliverTx"),
+            Some(Value::EndBlock(_)) => Self::Consensus("EndBlock"),
+            Some(Value::Commit(_)) => Self::Consensus("Commit"),
+            Some(Value::Echo(_)) => Self::Other("Echo"),
+            Some(Value::Flush(_)) => Self::Other("Flush"),
+            Some(Value::Info(_)) => Self::Other("Info"),
+            Some(Value::SetOption(_)) => Self::Other("SetOption"),
+            Some(Value::ListSnapshots(_)) => Self::ListSnapshots,
+            Some(Value::OfferSnapshot(_)) => Self::OfferSnapshot,
+            Some(Value::LoadSnapshotChunk(_)) => Self::LoadSnapshotChunk,
+            Some(Value::ApplySnapshotChunk(_)) => Self::ApplySnapshotChunk,
+            None => Self::Other("empty"),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::CheckTx => "CheckTx",
+            Self::Query => "Query",
+            Self::ListSnapshots => "ListSnapshots",
+            Self::OfferSnapshot => "OfferSnapshot",
+            Self::LoadSnapshotChunk => "LoadSnapshotChunk",
+            Self::ApplySnapshotChunk => "ApplySnapshotChunk",
+            Self::Consensus(name) | Self::Other(name) => name,
+        }
+    }
+
+    /// The response to send after the application panicked with the given
+    /// message, or `None` if the connection must be closed instead.
+    fn panic_response(self, message: &str) -> Option<Response> {
+        let log = format!("panic: {}", message);
+        let value = match self {
+            Self::CheckTx => {
+                response::Value::CheckTx(ResponseCheckTx::error(PANIC_RESPONSE_CODE, "", log))
+            },
+            Self::Query => response::Value::Query(ResponseQuery {
+                code: PANIC_RESPONSE_CODE,
+                log,
+                ..Default::default()
+            }),
+            Self::ListSnapshots => response::Value::ListSnapshots(Default::default()),
+            Self::OfferSnapshot => response::Value::OfferSnapshot(ResponseOfferSnapshot {
+                result: response_offer_snapshot::Result::Reject as i32,
+            }),
+            Self::LoadSnapshotChunk => response::Value::LoadSnapshotChunk(Default::default()),
+            Self::ApplySnapshotChunk => {
+                response::Value::ApplySnapshotChunk(ResponseApplySnapshotChunk {
+                    result: response_apply_snapshot_chunk::Result::Abort as i32,
+                    ..Default::default()
+                })
+            },
+            Self::Consensus(_) => return None,
+            Self::Other(_) => response::Value::Exception(ResponseException { error: log }),
+        };
+        Some(Response { value: Some(value) })
+    }
+}
+
+/// Extract the message from the given panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
+}
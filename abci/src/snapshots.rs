@@ -0,0 +1,221 @@
+//! Snapshot management for ABCI applications acting as state sync providers.
+//!
+//! A [`SnapshotStore`] splits serialized application state into chunks and
+//! serves them back in accordance with the semantics of the
+//! `ListSnapshots` and `LoadSnapshotChunk` ABCI methods. Each snapshot's
+//! metadata contains the SHA256 hashes of its chunks (concatenated), which
+//! allows restoring nodes to validate chunks individually via
+//! [`verify_chunk`]. The snapshot hash is the SHA256 hash of this metadata.
+
+use std::{
+    cmp::Reverse,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use bytes::Bytes;
+use prost::Message;
+use sha2::{Digest, Sha256};
+use tendermint_proto::abci::Snapshot;
+
+use crate::Error;
+
+/// The default maximum size of a snapshot chunk, in bytes (10MB). Tendermint
+/// rejects chunks larger than 16MB.
+pub const DEFAULT_SNAPSHOT_CHUNK_SIZE: usize = 10 * 1024 * 1024;
+
+/// The length of each chunk hash in a snapshot's metadata.
+const CHUNK_HASH_LENGTH: usize = 32;
+
+/// The name of the file holding a snapshot's (Protobuf-encoded) description.
+const SNAPSHOT_FILE_NAME: &str = "snapshot";
+
+/// The name of the temporary file to which a snapshot's description is
+/// written, before being moved into place.
+const SNAPSHOT_TMP_FILE_NAME: &str = "snapshot.tmp";
+
+/// Storage for application state snapshots.
+///
+/// Methods take `&self` so that stores can be shared between the clones of an
+/// [`Application`], which are used to serve different ABCI connections.
+///
+/// [`Application`]: crate::Application
+pub trait SnapshotStore {
+    /// Split the given serialized application state into chunks and store it
+    /// as a snapshot at the given height, in the given application-specific
+    /// format.
+    fn create(&self, height: u64, format: u32, state: &[u8]) -> Result<Snapshot, Error>;
+
+    /// List all available snapshots, most recent first.
+    fn list(&self) -> Result<Vec<Snapshot>, Error>;
+
+    /// Load the chunk with the given index from the snapshot at the given
+    /// height and format, if it exists.
+    fn load_chunk(&self, height: u64, format: u32, chunk: u32) -> Result<Option<Bytes>, Error>;
+
+    /// Delete the snapshot at the given height and format, if it exists.
+    fn delete(&self, height: u64, format: u32) -> Result<(), Error>;
+
+    /// Delete all but the `keep_recent` most recent snapshots.
+    fn prune(&self, keep_recent: usize) -> Result<(), Error> {
+        for snapshot in self.list()?.into_iter().skip(keep_recent) {
+            self.delete(snapshot.height, snapshot.format)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check whether the given chunk is the one with the given index in the given
+/// snapshot, as described by the snapshot's metadata.
+///
+/// Only applicable to snapshots produced by a [`SnapshotStore`] from this
+/// module.
+pub fn verify_chunk(snapshot: &Snapshot, index: u32, chunk: &[u8]) -> bool {
+    let start = index as usize * CHUNK_HASH_LENGTH;
+    match snapshot.metadata.get(start..start + CHUNK_HASH_LENGTH) {
+        Some(expected) => Sha256::digest(chunk).as_slice() == expected,
+        None => false,
+    }
+}
+
+/// A [`SnapshotStore`] that keeps snapshots on the local filesystem.
+///
+/// Each snapshot is stored in its own directory, `<root>/<height>/<format>`,
+/// containing one file per chunk as well as the snapshot's description.
+#[derive(Debug, Clone)]
+pub struct FsSnapshotStore {
+    root: PathBuf,
+    chunk_size: usize,
+}
+
+impl FsSnapshotStore {
+    /// Constructor.
+    ///
+    /// Snapshots will be stored under the given root directory, which will be
+    /// created if it does not exist.
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self, Error> {
+        fs::create_dir_all(root.as_ref()).map_err(Error::io)?;
+        Ok(Self {
+            root: root.as_ref().to_path_buf(),
+            chunk_size: DEFAULT_SNAPSHOT_CHUNK_SIZE,
+        })
+    }
+
+    /// Set the maximum size, in bytes, of the chunks of newly created
+    /// snapshots.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    fn snapshot_dir(&self, height: u64, format: u32) -> PathBuf {
+        self.root.join(height.to_string()).join(format.to_string())
+    }
+
+    /// The subdirectories of the given directory whose names parse as `T`,
+    /// ignoring any other entries (e.g. files created by other tools).
+    fn snapshot_dirs<T: FromStr>(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        let mut dirs = Vec::new();
+        for entry in fs::read_dir(dir).map_err(Error::io)? {
+            let path = entry.map_err(Error::io)?.path();
+            let parses = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<T>().ok())
+                .is_some();
+            if parses && path.is_dir() {
+                dirs.push(path);
+            }
+        }
+        Ok(dirs)
+    }
+
+    fn read_snapshot(dir: &Path) -> Result<Option<Snapshot>, Error> {
+        match fs::read(dir.join(SNAPSHOT_FILE_NAME)) {
+            Ok(buf) => Ok(Some(
+                Snapshot::decode(buf.as_slice()).map_err(Error::decode)?,
+            )),
+            // The description is written last, so its absence indicates an
+            // incomplete snapshot.
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::io(e)),
+        }
+    }
+}
+
+impl SnapshotStore for FsSnapshotStore {
+    fn create(&self, height: u64, format: u32, state: &[u8]) -> Result<Snapshot, Error> {
+        // Replace any existing snapshot, which may consist of more chunks
+        self.delete(height, format)?;
+        let dir = self.snapshot_dir(height, format);
+        fs::create_dir_all(&dir).map_err(Error::io)?;
+
+        // Tendermint requires snapshots to consist of at least one chunk
+        let chunks = if state.is_empty() {
+            vec![state]
+        } else {
+            state.chunks(self.chunk_size).collect()
+        };
+        let mut metadata = Vec::with_capacity(chunks.len() * CHUNK_HASH_LENGTH);
+        for (index, chunk) in chunks.iter().enumerate() {
+            fs::write(dir.join(index.to_string()), chunk).map_err(Error::io)?;
+            metadata.extend_from_slice(&Sha256::digest(chunk));
+        }
+
+        let snapshot = Snapshot {
+            height,
+            format,
+            chunks: chunks.len() as u32,
+            hash: Bytes::copy_from_slice(&Sha256::digest(&metadata)),
+            metadata: metadata.into(),
+        };
+        // Atomically move the description into place, so that an interrupted
+        // write cannot leave behind a truncated description
+        let tmp_path = dir.join(SNAPSHOT_TMP_FILE_NAME);
+        fs::write(&tmp_path, snapshot.encode_to_vec()).map_err(Error::io)?;
+        fs::rename(tmp_path, dir.join(SNAPSHOT_FILE_NAME)).map_err(Error::io)?;
+        Ok(snapshot)
+    }
+
+    fn list(&self) -> Result<Vec<Snapshot>, Error> {
+        let mut snapshots = Vec::new();
+        for height_dir in Self::snapshot_dirs::<u64>(&self.root)? {
+            for format_dir in Self::snapshot_dirs::<u32>(&height_dir)? {
+                if let Some(snapshot) = Self::read_snapshot(&format_dir)? {
+                    snapshots.push(snapshot);
+                }
+            }
+        }
+        snapshots.sort_by_key(|s| Reverse((s.height, s.format)));
+        Ok(snapshots)
+    }
+
+    fn load_chunk(&self, height: u64, format: u32, chunk: u32) -> Result<Option<Bytes>, Error> {
+        let path = self.snapshot_dir(height, format).join(chunk.to_string());
+        match fs::read(path) {
+            Ok(buf) => Ok(Some(buf.into())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::io(e)),
+        }
+    }
+
+    fn delete(&self, height: u64, format: u32) -> Result<(), Error> {
+        let height_dir = self.root.join(height.to_string());
+        match fs::remove_dir_all(height_dir.join(format.to_string())) {
+            Ok(()) => (),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(Error::io(e)),
+        }
+        // Clean up the height directory if this was its last snapshot
+        if fs::read_dir(&height_dir)
+            .map_err(Error::io)?
+            .next()
+            .is_none()
+        {
+            fs::remove_dir(&height_dir).map_err(Error::io)?;
+        }
+        Ok(())
+    }
+}
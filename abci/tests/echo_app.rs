@@ -2,8 +2,24 @@
 
 #[cfg(all(feature = "client", feature = "echo-app"))]
 mod echo_app_integration {
-    use tendermint_abci::{ClientBuilder, EchoApp, ServerBuilder};
-    use tendermint_proto::abci::RequestEcho;
+    use std::sync::{Arc, Mutex};
+
+    use bytes::Bytes;
+    use tendermint_abci::{error::ErrorDetail, Application, ClientBuilder, EchoApp, ServerBuilder};
+    use tendermint_proto::abci::{RequestCheckTx, RequestEcho, ResponseCheckTx};
+
+    /// Keeps every transaction it is asked to check.
+    #[derive(Clone, Default)]
+    struct RetainingApp {
+        txs: Arc<Mutex<Vec<Bytes>>>,
+    }
+
+    impl Application for RetainingApp {
+        fn check_tx(&self, request: RequestCheckTx) -> ResponseCheckTx {
+            self.txs.lock().unwrap().push(request.tx);
+            Default::default()
+        }
+    }
 
     #[test]
     fn echo() {
@@ -21,4 +37,88 @@ mod echo_app_integration {
             .unwrap();
         assert_eq!(response.message, "Hello ABCI!");
     }
+
+    #[test]
+    fn echo_large_message() {
+        // Use small read buffers to force messages to be received in many
+        // chunks
+        let server = ServerBuilder::new(1024)
+            .bind("127.0.0.1:0", EchoApp::default())
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::new(1024).connect(server_addr).unwrap();
+
+        let message = "a".repeat(4 * 1024 * 1024);
+        for _ in 0..2 {
+            let response = client
+                .echo(RequestEcho {
+                    message: message.clone(),
+                })
+                .unwrap();
+            assert_eq!(response.message, message);
+        }
+    }
+
+    #[test]
+    fn retained_txs_do_not_reference_read_buffer() {
+        let app = RetainingApp::default();
+        let server = ServerBuilder::default()
+            .bind("127.0.0.1:0", app.clone())
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+
+        for tx in ["a", "b", "c"] {
+            client.check_tx(RequestCheckTx::new(tx)).unwrap();
+        }
+
+        // Transactions sharing the connection's read buffer would keep the
+        // entire buffer alive for as long as the application holds them
+        let txs = app.txs.lock().unwrap();
+        assert_eq!(txs.len(), 3);
+        assert!(txs.iter().all(Bytes::is_unique));
+    }
+
+    #[test]
+    fn client_rejects_oversized_request() {
+        let server = ServerBuilder::default()
+            .bind("127.0.0.1:0", EchoApp::default())
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default()
+            .max_message_size(64)
+            .connect(server_addr)
+            .unwrap();
+
+        let err = client
+            .echo(RequestEcho {
+                message: "a".repeat(128),
+            })
+            .unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::MessageTooLong(_)));
+    }
+
+    #[test]
+    fn server_drops_oversized_request() {
+        let server = ServerBuilder::default()
+            .max_message_size(64)
+            .bind("127.0.0.1:0", EchoApp::default())
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+
+        let err = client
+            .echo(RequestEcho {
+                message: "a".repeat(128),
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err.detail(),
+            ErrorDetail::ServerConnectionTerminated(_) | ErrorDetail::Io(_)
+        ));
+    }
 }
@@ -0,0 +1,87 @@
+//! Handshake bookkeeping integration tests.
+
+mod handshake_integration {
+    use tendermint_abci::{error::ErrorDetail, Handshake};
+    use tendermint_proto::abci::{RequestInfo, RequestInitChain};
+
+    fn init_chain(initial_height: i64) -> RequestInitChain {
+        RequestInitChain {
+            initial_height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn initial_height_defaults_to_one() {
+        let handshake = Handshake::new("app", "0.1.0", 1);
+        handshake.init_chain(&init_chain(0)).unwrap();
+        assert!(handshake.begin_block(0).is_err());
+        handshake.begin_block(1).unwrap();
+    }
+
+    #[test]
+    fn initial_height_is_honored() {
+        let handshake = Handshake::new("app", "0.1.0", 1);
+        handshake.init_chain(&init_chain(100)).unwrap();
+
+        let err = handshake.begin_block(1).unwrap_err();
+        match err.detail() {
+            ErrorDetail::UnexpectedBlockHeight(e) => {
+                assert_eq!(e.expected, 100);
+                assert_eq!(e.got, 1);
+            },
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        handshake.begin_block(100).unwrap();
+        assert_eq!(handshake.commit("hash"), 100);
+        handshake.begin_block(101).unwrap();
+    }
+
+    #[test]
+    fn restored_chain_cannot_be_initialized() {
+        let handshake = Handshake::new("app", "0.1.0", 1)
+            .with_last_block(42, "hash")
+            .unwrap();
+
+        let err = handshake.init_chain(&init_chain(1)).unwrap_err();
+        match err.detail() {
+            ErrorDetail::ChainAlreadyInitialized(e) => assert_eq!(e.last_block_height, 42),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        handshake.begin_block(43).unwrap();
+    }
+
+    #[test]
+    fn info_reports_last_block() {
+        let handshake = Handshake::new("app", "0.1.0", 7)
+            .with_last_block(42, "restored")
+            .unwrap();
+
+        let response = handshake.info(&RequestInfo::default());
+        assert_eq!(response.data, "app");
+        assert_eq!(response.version, "0.1.0");
+        assert_eq!(response.app_version, 7);
+        assert_eq!(response.last_block_height, 42);
+        assert_eq!(response.last_block_app_hash.as_ref(), b"restored");
+
+        handshake.begin_block(43).unwrap();
+        handshake.commit("committed");
+        let response = handshake.info(&RequestInfo::default());
+        assert_eq!(response.last_block_height, 43);
+        assert_eq!(response.last_block_app_hash.as_ref(), b"committed");
+    }
+
+    #[test]
+    fn invalid_last_block_height_is_rejected() {
+        for height in [-1, i64::MAX] {
+            let err = Handshake::new("app", "0.1.0", 1)
+                .with_last_block(height, "hash")
+                .unwrap_err();
+            match err.detail() {
+                ErrorDetail::InvalidBlockHeight(e) => assert_eq!(e.height, height),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+}
@@ -5,7 +5,10 @@ mod kvstore_app_integration {
     use std::thread;
 
     use tendermint_abci::{ClientBuilder, KeyValueStoreApp, ServerBuilder};
-    use tendermint_proto::abci::{RequestDeliverTx, RequestEcho, RequestQuery};
+    use tendermint_proto::{
+        abci::{RequestBeginBlock, RequestDeliverTx, RequestEcho, RequestInfo, RequestQuery},
+        types::Header,
+    };
 
     #[test]
     fn happy_path() {
@@ -39,5 +42,32 @@ mod kvstore_app_integration {
             })
             .unwrap();
         assert_eq!(res.value, "test-value".as_bytes());
+        assert_eq!(res.height, 1);
+
+        let res = client.info(RequestInfo::default()).unwrap();
+        assert_eq!(res.last_block_height, 1);
+        assert_eq!(res.last_block_app_hash.as_ref(), [1]);
+    }
+
+    #[test]
+    fn rejects_unexpected_block() {
+        let (app, driver) = KeyValueStoreApp::new();
+        let server = ServerBuilder::default().bind("127.0.0.1:0", app).unwrap();
+        let server_addr = server.local_addr();
+        thread::spawn(move || driver.run());
+        thread::spawn(move || server.listen());
+
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+        let begin_block = |height| RequestBeginBlock {
+            header: Some(Header {
+                height,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        client.begin_block(begin_block(1)).unwrap();
+        client.commit().unwrap();
+        // Tendermint must not skip blocks
+        assert!(client.begin_block(begin_block(3)).is_err());
     }
 }
@@ -0,0 +1,162 @@
+//! Application panic isolation integration tests.
+
+#[cfg(feature = "client")]
+mod panics_integration {
+    use tendermint_abci::{
+        error::ErrorDetail, recording::RecordReader, Application, ClientBuilder, ServerBuilder,
+    };
+    use tendermint_proto::abci::{
+        request, response, response_apply_snapshot_chunk, response_offer_snapshot,
+        RequestApplySnapshotChunk, RequestCheckTx, RequestEcho, RequestInfo,
+        RequestLoadSnapshotChunk, RequestOfferSnapshot, RequestQuery, ResponseApplySnapshotChunk,
+        ResponseCheckTx, ResponseCommit, ResponseInfo, ResponseListSnapshots,
+        ResponseLoadSnapshotChunk, ResponseOfferSnapshot, ResponseQuery,
+    };
+
+    #[derive(Clone)]
+    struct PanickingApp;
+
+    impl Application for PanickingApp {
+        fn info(&self, _request: RequestInfo) -> ResponseInfo {
+            panic!("info")
+        }
+
+        fn query(&self, _request: RequestQuery) -> ResponseQuery {
+            panic!("query")
+        }
+
+        fn check_tx(&self, _request: RequestCheckTx) -> ResponseCheckTx {
+            panic!("check_tx")
+        }
+
+        fn commit(&self) -> ResponseCommit {
+            panic!("commit")
+        }
+
+        fn list_snapshots(&self) -> ResponseListSnapshots {
+            panic!("list_snapshots")
+        }
+
+        fn offer_snapshot(&self, _request: RequestOfferSnapshot) -> ResponseOfferSnapshot {
+            panic!("offer_snapshot")
+        }
+
+        fn load_snapshot_chunk(
+            &self,
+            _request: RequestLoadSnapshotChunk,
+        ) -> ResponseLoadSnapshotChunk {
+            panic!("load_snapshot_chunk")
+        }
+
+        fn apply_snapshot_chunk(
+            &self,
+            _request: RequestApplySnapshotChunk,
+        ) -> ResponseApplySnapshotChunk {
+            panic!("apply_snapshot_chunk")
+        }
+    }
+
+    #[test]
+    fn panics_are_isolated() {
+        let server = ServerBuilder::default()
+            .bind("127.0.0.1:0", PanickingApp)
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+
+        let response = client.check_tx(RequestCheckTx::new("tx")).unwrap();
+        assert!(!response.is_ok());
+        assert_eq!(response.log, "panic: check_tx");
+
+        let response = client.query(RequestQuery::default()).unwrap();
+        assert_ne!(response.code, 0);
+        assert_eq!(response.log, "panic: query");
+
+        let err = client.info(RequestInfo::default()).unwrap_err();
+        match err.detail() {
+            ErrorDetail::UnexpectedServerResponseType(e) => assert!(matches!(
+                &e.got,
+                response::Value::Exception(e) if e.error == "panic: info"
+            )),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // The server keeps the connection open after the panics above (although
+        // Tendermint itself closes it upon receiving an exception response)
+        let response = client
+            .echo(RequestEcho {
+                message: "still alive".to_string(),
+            })
+            .unwrap();
+        assert_eq!(response.message, "still alive");
+
+        // ... but not a panic during block execution
+        assert!(client.commit().is_err());
+        assert!(client
+            .echo(RequestEcho {
+                message: "dead".to_string(),
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn state_sync_panics_are_not_fatal() {
+        let server = ServerBuilder::default()
+            .bind("127.0.0.1:0", PanickingApp)
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+
+        assert!(client.list_snapshots().unwrap().snapshots.is_empty());
+        assert_eq!(
+            client
+                .offer_snapshot(RequestOfferSnapshot::default())
+                .unwrap()
+                .result,
+            response_offer_snapshot::Result::Reject as i32
+        );
+        assert!(client
+            .load_snapshot_chunk(RequestLoadSnapshotChunk::default())
+            .unwrap()
+            .chunk
+            .is_empty());
+        assert_eq!(
+            client
+                .apply_snapshot_chunk(RequestApplySnapshotChunk::default())
+                .unwrap()
+                .result,
+            response_apply_snapshot_chunk::Result::Abort as i32
+        );
+    }
+
+    #[test]
+    fn consensus_panics_are_recorded() {
+        let path =
+            std::env::temp_dir().join(format!("tendermint-abci-panics-{}.bin", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let server = ServerBuilder::default()
+            .record(&path)
+            .bind("127.0.0.1:0", PanickingApp)
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+        assert!(client.commit().is_err());
+
+        let records = RecordReader::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(matches!(
+            records[0].request.as_ref().and_then(|r| r.value.as_ref()),
+            Some(request::Value::Commit(_))
+        ));
+        assert_eq!(records[0].response, None);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
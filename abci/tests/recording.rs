@@ -0,0 +1,194 @@
+//! Request recording and replay integration tests.
+
+#[cfg(all(feature = "client", feature = "echo-app"))]
+mod recording_integration {
+    use tendermint_abci::{
+        error::ErrorDetail,
+        recording::{max_record_size, replay, RecordReader},
+        Application, ClientBuilder, EchoApp, ServerBuilder,
+    };
+    use tendermint_proto::abci::{RequestEcho, ResponseEcho};
+
+    #[derive(Clone)]
+    struct ShoutingEchoApp;
+
+    /// Echoes every message twice.
+    #[derive(Clone)]
+    struct DoublingEchoApp;
+
+    impl Application for DoublingEchoApp {
+        fn echo(&self, request: RequestEcho) -> ResponseEcho {
+            ResponseEcho {
+                message: request.message.repeat(2),
+            }
+        }
+    }
+
+    impl Application for ShoutingEchoApp {
+        fn echo(&self, request: RequestEcho) -> ResponseEcho {
+            ResponseEcho {
+                message: request.message.to_uppercase(),
+            }
+        }
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let path = std::env::temp_dir().join(format!(
+            "tendermint-abci-recording-{}.bin",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let server = ServerBuilder::default()
+            .record(&path)
+            .bind("127.0.0.1:0", EchoApp::default())
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+        for message in ["Hello", "abci"] {
+            client
+                .echo(RequestEcho {
+                    message: message.to_string(),
+                })
+                .unwrap();
+        }
+
+        let records = RecordReader::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+
+        let divergences = replay(&EchoApp::default(), RecordReader::open(&path).unwrap()).unwrap();
+        assert!(divergences.is_empty());
+
+        let divergences = replay(&ShoutingEchoApp, RecordReader::open(&path).unwrap()).unwrap();
+        assert_eq!(
+            divergences.iter().map(|d| d.index).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_recording_with_raised_message_size() {
+        let path = std::env::temp_dir().join(format!(
+            "tendermint-abci-recording-raised-{}.bin",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let max_message_size = 512 * 1024 * 1024;
+
+        let server = ServerBuilder::default()
+            .max_message_size(max_message_size)
+            .record(&path)
+            .bind("127.0.0.1:0", EchoApp::default())
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+        client
+            .echo(RequestEcho {
+                message: "a".repeat(1024),
+            })
+            .unwrap();
+
+        let records = RecordReader::open(&path)
+            .unwrap()
+            .with_max_record_size(max_record_size(max_message_size))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+
+        // The record contains both the request and the response
+        let err = RecordReader::open(&path)
+            .unwrap()
+            .with_max_record_size(1024)
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::MessageTooLong(_)));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn record_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "tendermint-abci-recording-{}-{}.bin",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn record_messages_of_maximum_size() {
+        let path = record_path("maximum");
+        let max_message_size = 1024;
+
+        let server = ServerBuilder::default()
+            .max_message_size(max_message_size)
+            .record(&path)
+            .bind("127.0.0.1:0", EchoApp::default())
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+
+        // Both the request and the response are exactly `max_message_size`
+        // bytes long
+        client
+            .echo(RequestEcho {
+                message: "a".repeat(1018),
+            })
+            .unwrap();
+
+        let records = RecordReader::open(&path)
+            .unwrap()
+            .with_max_record_size(max_record_size(max_message_size))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records[0].response.is_some());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn record_oversized_response() {
+        let path = record_path("oversized");
+        let max_message_size = 1024;
+
+        let server = ServerBuilder::default()
+            .max_message_size(max_message_size)
+            .record(&path)
+            .bind("127.0.0.1:0", DoublingEchoApp)
+            .unwrap();
+        let server_addr = server.local_addr();
+        let _ = std::thread::spawn(move || server.listen());
+        let mut client = ClientBuilder::default().connect(server_addr).unwrap();
+
+        // The response exceeds `max_message_size`, so the server closes the
+        // connection
+        assert!(client
+            .echo(RequestEcho {
+                message: "a".repeat(600),
+            })
+            .is_err());
+
+        let records = RecordReader::open(&path)
+            .unwrap()
+            .with_max_record_size(max_record_size(max_message_size))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records[0].request.is_some());
+        assert_eq!(records[0].response, None);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
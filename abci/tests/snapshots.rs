@@ -0,0 +1,75 @@
+//! Snapshot store integration tests.
+
+#[cfg(feature = "snapshots")]
+mod snapshots_integration {
+    use std::path::PathBuf;
+
+    use tendermint_abci::snapshots::{verify_chunk, FsSnapshotStore, SnapshotStore};
+
+    fn store_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "tendermint-abci-snapshots-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        root
+    }
+
+    #[test]
+    fn create_load_and_prune() {
+        let root = store_root("create_load_and_prune");
+        let store = FsSnapshotStore::new(&root).unwrap().with_chunk_size(4);
+
+        let state = b"0123456789";
+        let snapshot = store.create(10, 1, state).unwrap();
+        assert_eq!(snapshot.chunks, 3);
+        store.create(20, 1, b"").unwrap();
+        store.create(30, 1, state).unwrap();
+
+        let heights = store
+            .list()
+            .unwrap()
+            .iter()
+            .map(|s| s.height)
+            .collect::<Vec<_>>();
+        assert_eq!(heights, vec![30, 20, 10]);
+
+        let mut restored = Vec::new();
+        for index in 0..snapshot.chunks {
+            let chunk = store.load_chunk(10, 1, index).unwrap().unwrap();
+            assert!(verify_chunk(&snapshot, index, &chunk));
+            restored.extend_from_slice(&chunk);
+        }
+        assert_eq!(restored, state);
+        assert!(!verify_chunk(&snapshot, 0, b"4567"));
+        assert!(store.load_chunk(10, 1, 3).unwrap().is_none());
+
+        store.prune(1).unwrap();
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert!(store.load_chunk(10, 1, 0).unwrap().is_none());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn list_ignores_unrelated_entries() {
+        let root = store_root("list_ignores_unrelated_entries");
+        let store = FsSnapshotStore::new(&root).unwrap();
+        store.create(10, 1, b"state").unwrap();
+
+        std::fs::write(root.join("notes.txt"), b"").unwrap();
+        std::fs::create_dir(root.join("tmp")).unwrap();
+        std::fs::write(root.join("10").join(".DS_Store"), b"").unwrap();
+        std::fs::create_dir(root.join("10").join("backup")).unwrap();
+        // An interrupted write of a snapshot's description
+        std::fs::create_dir_all(root.join("20").join("1")).unwrap();
+        std::fs::write(root.join("20").join("1").join("snapshot.tmp"), b"\x08").unwrap();
+
+        let snapshots = store.list().unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!((snapshots[0].height, snapshots[0].format), (10, 1));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
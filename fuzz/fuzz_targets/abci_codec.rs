@@ -0,0 +1,17 @@
+//! Feeds arbitrary bytes through the ABCI length-delimited framing, as used
+//! to read recordings of ABCI exchanges.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tendermint_abci::recording::RecordReader;
+
+fuzz_target!(|data: &[u8]| {
+    // Decoding stops at the first error, as the framing cannot be recovered
+    // from that point onwards.
+    for record in RecordReader::new(data) {
+        if record.is_err() {
+            break;
+        }
+    }
+});
@@ -0,0 +1,28 @@
+//! Decodes arbitrary bytes as Protobuf-encoded Tendermint messages, and
+//! checks that successfully decoded messages survive an encoding round trip.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+use tendermint_proto::{
+    abci::{Request, Response},
+    types::{Block, LightBlock, SignedHeader, ValidatorSet},
+};
+
+fn round_trip<M: Message + Default + PartialEq>(data: &[u8]) {
+    if let Ok(message) = M::decode(data) {
+        let encoded = message.encode_to_vec();
+        let decoded = M::decode(encoded.as_slice()).expect("re-encoded message must decode");
+        assert!(decoded == message, "message changed during round trip");
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    round_trip::<Request>(data);
+    round_trip::<Response>(data);
+    round_trip::<Block>(data);
+    round_trip::<LightBlock>(data);
+    round_trip::<SignedHeader>(data);
+    round_trip::<ValidatorSet>(data);
+});
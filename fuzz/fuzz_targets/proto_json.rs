@@ -0,0 +1,27 @@
+//! Deserializes arbitrary bytes as JSON-encoded Tendermint types, exercising
+//! the custom serializers in `tendermint_proto::serializers`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde::de::DeserializeOwned;
+use tendermint_proto::types::{Block, BlockId, Commit, Header, SignedHeader, ValidatorSet, Vote};
+
+fn deserialize<T: DeserializeOwned + serde::Serialize>(data: &[u8]) {
+    if let Ok(value) = serde_json::from_slice::<T>(data) {
+        // Serialization may legitimately fail (e.g. for out-of-range
+        // timestamps), but must not panic.
+        let _ = serde_json::to_vec(&value);
+        let _ = tendermint_proto::serializers::canonical_json::to_vec(&value);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    deserialize::<Block>(data);
+    deserialize::<BlockId>(data);
+    deserialize::<Commit>(data);
+    deserialize::<Header>(data);
+    deserialize::<SignedHeader>(data);
+    deserialize::<ValidatorSet>(data);
+    deserialize::<Vote>(data);
+});
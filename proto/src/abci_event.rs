@@ -0,0 +1,105 @@
+//! Ergonomic construction of ABCI events.
+//!
+//! Event attribute keys and values are transmitted as raw bytes, but are
+//! UTF-8 strings in practice. These helpers take care of the conversion in
+//! both directions.
+
+use core::str::Utf8Error;
+
+use bytes::Bytes;
+
+use crate::{
+    abci::{Event, EventAttribute},
+    prelude::*,
+};
+
+impl Event {
+    /// Construct an event of the given type, without any attributes.
+    ///
+    /// Attributes can be added using [`Event::attr`] and
+    /// [`Event::attr_indexed`], e.g.:
+    ///
+    /// ```rust
+    /// use tendermint_proto::abci::Event;
+    ///
+    /// let event = Event::new("transfer")
+    ///     .attr("memo", "thanks!")
+    ///     .attr_indexed("sender", "alice")
+    ///     .attr_indexed("amount", "100");
+    /// assert_eq!(event.attributes.len(), 3);
+    /// assert!(!event.attributes[0].index);
+    /// assert!(event.attributes[1].index);
+    /// ```
+    pub fn new<T: Into<String>>(r#type: T) -> Self {
+        Self {
+            r#type: r#type.into(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Append an attribute that will not be indexed by Tendermint.
+    pub fn attr<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.attributes.push(EventAttribute::new(key, value, false));
+        self
+    }
+
+    /// Append an attribute that will be indexed by Tendermint, allowing
+    /// transactions and blocks to be searched by it.
+    pub fn attr_indexed<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.attributes.push(EventAttribute::new(key, value, true));
+        self
+    }
+}
+
+impl EventAttribute {
+    /// Construct an event attribute from UTF-8 strings.
+    pub fn new<K, V>(key: K, value: V, index: bool) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            key: Bytes::from(key.into()),
+            value: Bytes::from(value.into()),
+            index,
+        }
+    }
+
+    /// Attempt to interpret this attribute's key as a UTF-8 string.
+    pub fn key_str(&self) -> Result<&str, Utf8Error> {
+        core::str::from_utf8(&self.key)
+    }
+
+    /// Attempt to interpret this attribute's value as a UTF-8 string.
+    pub fn value_str(&self) -> Result<&str, Utf8Error> {
+        core::str::from_utf8(&self.value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn attributes_round_trip_as_utf8() {
+        let event = Event::new("app").attr_indexed("key", "värde");
+        let attr = &event.attributes[0];
+        assert_eq!(attr.key_str().unwrap(), "key");
+        assert_eq!(attr.value_str().unwrap(), "värde");
+        assert!(attr.index);
+
+        let invalid = EventAttribute {
+            key: Bytes::from_static(&[0xFF, 0xFE]),
+            ..attr.clone()
+        };
+        assert!(invalid.key_str().is_err());
+    }
+}
@@ -0,0 +1,46 @@
+//! Ergonomic construction and inspection of ABCI requests.
+
+use bytes::Bytes;
+
+use crate::abci::{CheckTxType, RequestCheckTx};
+
+impl RequestCheckTx {
+    /// Construct a request to check a transaction that is new to the mempool.
+    pub fn new<T: Into<Bytes>>(tx: T) -> Self {
+        Self {
+            tx: tx.into(),
+            r#type: CheckTxType::New.into(),
+        }
+    }
+
+    /// Construct a request to recheck a transaction that is already in the
+    /// mempool, as Tendermint does for all remaining mempool transactions
+    /// after each block is committed.
+    pub fn recheck<T: Into<Bytes>>(tx: T) -> Self {
+        Self {
+            tx: tx.into(),
+            r#type: CheckTxType::Recheck.into(),
+        }
+    }
+
+    /// Whether this request is a recheck of a transaction already in the
+    /// mempool.
+    ///
+    /// Applications may skip expensive checks (e.g. signature verification)
+    /// for rechecks, since these were already performed when the transaction
+    /// was first checked.
+    pub fn is_recheck(&self) -> bool {
+        self.r#type() == CheckTxType::Recheck
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recheck_requests_are_distinguished() {
+        assert!(!RequestCheckTx::new("tx").is_recheck());
+        assert!(RequestCheckTx::recheck("tx").is_recheck());
+    }
+}
@@ -0,0 +1,104 @@
+//! Ergonomic construction of `CheckTx` and `DeliverTx` responses.
+
+use bytes::Bytes;
+
+use crate::{
+    abci::{Event, ResponseCheckTx, ResponseDeliverTx},
+    prelude::*,
+};
+
+/// The response code indicating that a transaction was processed
+/// successfully. Any other code indicates an error.
+const CODE_TYPE_OK: u32 = 0;
+
+macro_rules! impl_tx_response {
+    ($type:ident, $method:literal) => {
+        impl $type {
+            #[doc = concat!("Construct a successful `", $method, "` response carrying the given")]
+            /// data and events.
+            pub fn success<D: Into<Bytes>>(data: D, events: Vec<Event>) -> Self {
+                Self {
+                    code: CODE_TYPE_OK,
+                    data: data.into(),
+                    events,
+                    ..Default::default()
+                }
+            }
+
+            #[doc = concat!("Construct a failed `", $method, "` response.")]
+            ///
+            /// The `code` is interpreted within the given `codespace`, and the
+            /// `log` should describe the failure to the user. It is the
+            /// caller's responsibility to use a non-zero `code`, as a code of 0
+            /// indicates success (see `is_ok`).
+            pub fn error<C, L>(code: u32, codespace: C, log: L) -> Self
+            where
+                C: Into<String>,
+                L: Into<String>,
+            {
+                Self {
+                    code,
+                    codespace: codespace.into(),
+                    log: log.into(),
+                    ..Default::default()
+                }
+            }
+
+            /// Set the amount of gas requested and consumed by the transaction.
+            pub fn with_gas(mut self, gas_wanted: i64, gas_used: i64) -> Self {
+                self.gas_wanted = gas_wanted;
+                self.gas_used = gas_used;
+                self
+            }
+
+            /// Set the (non-deterministic) log message.
+            pub fn with_log<L: Into<String>>(mut self, log: L) -> Self {
+                self.log = log.into();
+                self
+            }
+
+            /// Whether this response indicates that the transaction was
+            /// processed successfully.
+            pub fn is_ok(&self) -> bool {
+                self.code == CODE_TYPE_OK
+            }
+        }
+    };
+}
+
+impl_tx_response!(ResponseCheckTx, "CheckTx");
+impl_tx_response!(ResponseDeliverTx, "DeliverTx");
+
+impl ResponseCheckTx {
+    /// Set the priority of the transaction in the mempool.
+    ///
+    /// Only taken into account by Tendermint's priority mempool, in which
+    /// transactions with higher priority are reaped first when proposing a
+    /// block.
+    pub fn with_priority(mut self, priority: i64) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the sender of the transaction, which the priority mempool uses
+    /// to allow only a single transaction per sender at a time.
+    pub fn with_sender<S: Into<String>>(mut self, sender: S) -> Self {
+        self.sender = sender.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn error_response_is_not_ok() {
+        let res = ResponseDeliverTx::error(2, "bank", "insufficient funds").with_gas(100, 40);
+        assert!(!res.is_ok());
+        assert_eq!(res.codespace, "bank");
+        assert_eq!(res.gas_wanted, 100);
+        assert_eq!(res.gas_used, 40);
+        assert!(ResponseCheckTx::success(Bytes::new(), vec![]).is_ok());
+    }
+}
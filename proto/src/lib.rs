@@ -18,6 +18,9 @@ pub mod google {
     }
 }
 
+mod abci_event;
+mod abci_request;
+mod abci_response;
 mod error;
 #[allow(warnings)]
 mod tendermint;
@@ -33,6 +36,7 @@ use prost::{encoding::encoded_len_varint, Message};
 pub use tendermint::*;
 
 pub mod serializers;
+pub mod sign_bytes;
 
 use prelude::*;
 
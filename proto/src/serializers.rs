@@ -53,6 +53,8 @@
 // Todo: remove dead_code allowance as soon as more types are implemented
 #![allow(dead_code)]
 pub mod bytes;
+#[cfg(feature = "canonical-json")]
+pub mod canonical_json;
 pub mod evidence;
 pub mod from_str;
 pub mod nullable;
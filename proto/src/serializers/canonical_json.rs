@@ -0,0 +1,111 @@
+//! Deterministic (canonical) JSON encoding of serializable types.
+//!
+//! The output is compatible with Go's sorted JSON encoding as used for
+//! JSON-based sign bytes (e.g. Amino JSON signing):
+//! * object keys are sorted lexicographically by their UTF-8 bytes,
+//! * no insignificant whitespace is emitted, and
+//! * the characters `<`, `>` and `&` as well as U+2028 and U+2029 are escaped
+//!   within strings, as Go's `encoding/json` does by default.
+//!
+//! Numbers and byte arrays are encoded as specified by each type's `Serialize`
+//! implementation (e.g. 64-bit integers as strings via [`from_str`]).
+//!
+//! Requires the `canonical-json` feature.
+//!
+//! [`from_str`]: super::from_str
+
+use serde::Serialize;
+use serde_json::{Error, Value};
+
+use crate::prelude::*;
+
+/// Serialize the given value into canonical JSON bytes.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    to_string(value).map(String::into_bytes)
+}
+
+/// Serialize the given value into a canonical JSON string.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let value = serde_json::to_value(value)?;
+    let mut out = String::new();
+    write_value(&value, &mut out)?;
+    Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut String) -> Result<(), Error> {
+    match value {
+        Value::Null | Value::Bool(_) | Value::Number(_) => {
+            out.push_str(&serde_json::to_string(value)?)
+        },
+        Value::String(s) => write_string(s, out)?,
+        Value::Array(values) => {
+            out.push('[');
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(v, out)?;
+            }
+            out.push(']');
+        },
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push('{');
+            for (i, (k, v)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(k, out)?;
+                out.push(':');
+                write_value(v, out)?;
+            }
+            out.push('}');
+        },
+    }
+    Ok(())
+}
+
+fn write_string(s: &str, out: &mut String) -> Result<(), Error> {
+    let escaped = serde_json::to_string(s)?;
+    for c in escaped.chars() {
+        match c {
+            '<' => out.push_str("\\u003c"),
+            '>' => out.push_str("\\u003e"),
+            '&' => out.push_str("\\u0026"),
+            '\u{2028}' => out.push_str("\\u2028"),
+            '\u{2029}' => out.push_str("\\u2029"),
+            c => out.push(c),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{BlockId, PartSetHeader};
+
+    #[test]
+    fn keys_are_sorted_and_whitespace_is_omitted() {
+        let block_id = BlockId {
+            hash: vec![0xAB, 0xCD],
+            part_set_header: Some(PartSetHeader {
+                total: 1,
+                hash: vec![0x01],
+            }),
+        };
+        assert_eq!(
+            to_string(&block_id).unwrap(),
+            r#"{"hash":"ABCD","part_set_header":{"hash":"01","total":1}}"#
+        );
+    }
+
+    #[test]
+    fn html_characters_are_escaped() {
+        assert_eq!(
+            to_string("<a & b>\u{2028}").unwrap(),
+            r#""\u003ca \u0026 b\u003e\u2028""#
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Canonical sign bytes for consensus messages.
+//!
+//! Validators do not sign [`Vote`]s and [`Proposal`]s directly, but rather the
+//! length-delimited Protobuf encoding of their canonical counterparts
+//! ([`CanonicalVote`] and [`CanonicalProposal`]), which additionally commit to
+//! the chain ID. External signers (e.g. HSMs or hardware wallets) can use the
+//! functions in this module to produce the exact bytes to be signed.
+
+use prost::{encoding::encoded_len_varint, Message};
+
+use crate::{
+    prelude::*,
+    types::{
+        BlockId, CanonicalBlockId, CanonicalPartSetHeader, CanonicalProposal, CanonicalVote,
+        PartSetHeader, Proposal, SignedMsgType, Vote,
+    },
+    Error,
+};
+
+impl From<PartSetHeader> for CanonicalPartSetHeader {
+    fn from(value: PartSetHeader) -> Self {
+        Self {
+            total: value.total,
+            hash: value.hash,
+        }
+    }
+}
+
+impl CanonicalBlockId {
+    /// Canonicalize the given block ID.
+    ///
+    /// Returns `None` for a zero block ID (i.e. one with an empty hash and an
+    /// empty part set header), which is how a vote for nil is represented.
+    pub fn new(block_id: Option<BlockId>) -> Option<Self> {
+        let block_id = block_id?;
+        let part_set_header = block_id.part_set_header.unwrap_or_default();
+        if block_id.hash.is_empty() && part_set_header.total == 0 && part_set_header.hash.is_empty()
+        {
+            return None;
+        }
+        Some(Self {
+            hash: block_id.hash,
+            part_set_header: Some(part_set_header.into()),
+        })
+    }
+}
+
+impl CanonicalVote {
+    /// Construct the canonical form of the given vote for the given chain.
+    pub fn new<C: Into<String>>(vote: Vote, chain_id: C) -> Self {
+        Self {
+            r#type: vote.r#type,
+            height: vote.height,
+            round: vote.round.into(),
+            block_id: CanonicalBlockId::new(vote.block_id),
+            timestamp: vote.timestamp,
+            chain_id: chain_id.into(),
+        }
+    }
+}
+
+impl CanonicalProposal {
+    /// Construct the canonical form of the given proposal for the given chain.
+    pub fn new<C: Into<String>>(proposal: Proposal, chain_id: C) -> Self {
+        Self {
+            r#type: SignedMsgType::Proposal.into(),
+            height: proposal.height,
+            round: proposal.round.into(),
+            pol_round: proposal.pol_round.into(),
+            block_id: CanonicalBlockId::new(proposal.block_id),
+            timestamp: proposal.timestamp,
+            chain_id: chain_id.into(),
+        }
+    }
+}
+
+/// Produce the bytes to be signed for the given vote on the given chain.
+///
+/// Any signature already present in the vote is ignored.
+pub fn vote_sign_bytes<C: Into<String>>(vote: Vote, chain_id: C) -> Result<Vec<u8>, Error> {
+    encode_length_delimited_vec(CanonicalVote::new(vote, chain_id))
+}
+
+/// Produce the bytes to be signed for the given proposal on the given chain.
+///
+/// Any signature already present in the proposal is ignored.
+pub fn proposal_sign_bytes<C: Into<String>>(
+    proposal: Proposal,
+    chain_id: C,
+) -> Result<Vec<u8>, Error> {
+    encode_length_delimited_vec(CanonicalProposal::new(proposal, chain_id))
+}
+
+fn encode_length_delimited_vec<M: Message>(message: M) -> Result<Vec<u8>, Error> {
+    let len = message.encoded_len();
+    let mut wire = Vec::with_capacity(len + encoded_len_varint(len as u64));
+    message
+        .encode_length_delimited(&mut wire)
+        .map_err(Error::encode_message)?;
+    Ok(wire)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::google::protobuf::Timestamp;
+
+    fn block_id() -> BlockId {
+        BlockId {
+            hash: vec![0xAB; 32],
+            part_set_header: Some(PartSetHeader {
+                total: 1,
+                hash: vec![0xCD; 32],
+            }),
+        }
+    }
+
+    #[test]
+    fn nil_vote_has_no_canonical_block_id() {
+        let vote = Vote {
+            r#type: SignedMsgType::Prevote.into(),
+            height: 1,
+            block_id: Some(BlockId::default()),
+            ..Default::default()
+        };
+        assert_eq!(CanonicalVote::new(vote, "test-chain").block_id, None);
+    }
+
+    #[test]
+    fn vote_sign_bytes_ignore_signature() {
+        let vote = Vote {
+            r#type: SignedMsgType::Precommit.into(),
+            height: 12345,
+            round: 2,
+            block_id: Some(block_id()),
+            timestamp: Some(Timestamp {
+                seconds: 1_600_000_000,
+                nanos: 0,
+            }),
+            validator_address: vec![0x01; 20],
+            validator_index: 3,
+            signature: vec![],
+        };
+        let signed = Vote {
+            signature: vec![0xFF; 64],
+            ..vote.clone()
+        };
+        let unsigned_bytes = vote_sign_bytes(vote, "test-chain").unwrap();
+        assert_eq!(
+            unsigned_bytes,
+            vote_sign_bytes(signed, "test-chain").unwrap()
+        );
+
+        let decoded = CanonicalVote::decode_length_delimited(unsigned_bytes.as_ref()).unwrap();
+        assert_eq!(decoded.height, 12345);
+        assert_eq!(decoded.round, 2);
+        assert_eq!(decoded.chain_id, "test-chain");
+    }
+
+    #[test]
+    fn proposal_sign_bytes_commit_to_chain_id() {
+        let proposal = Proposal {
+            r#type: SignedMsgType::Proposal.into(),
+            height: 2,
+            round: 0,
+            pol_round: -1,
+            block_id: Some(block_id()),
+            ..Default::default()
+        };
+        let a = proposal_sign_bytes(proposal.clone(), "chain-a").unwrap();
+        let b = proposal_sign_bytes(proposal, "chain-b").unwrap();
+        assert_ne!(a, b);
+
+        let decoded = CanonicalProposal::decode_length_delimited(a.as_ref()).unwrap();
+        assert_eq!(decoded.r#type, SignedMsgType::Proposal as i32);
+        assert_eq!(decoded.pol_round, -1);
+    }
+}
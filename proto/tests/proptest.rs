@@ -0,0 +1,196 @@
+//! Property-based round-trip tests for the Protobuf and JSON encodings of
+//! core Tendermint types.
+
+use core::fmt::Debug;
+
+use proptest::{collection::vec, option, prelude::*};
+use prost::Message;
+use serde::{de::DeserializeOwned, Serialize};
+use tendermint_proto::{
+    crypto::{public_key::Sum, PublicKey},
+    google::protobuf::Timestamp,
+    types::{
+        BlockId, BlockIdFlag, Commit, CommitSig, Header, PartSetHeader, SignedMsgType, Validator,
+        ValidatorSet, Vote,
+    },
+    version::Consensus,
+};
+
+/// Timestamps within the range representable as RFC 3339 strings
+/// (0001-01-01T00:00:00Z to 9999-12-31T23:59:59.999999999Z).
+fn timestamp() -> impl Strategy<Value = Timestamp> {
+    (-62_135_596_800_i64..=253_402_300_799, 0..1_000_000_000_i32)
+        .prop_map(|(seconds, nanos)| Timestamp { seconds, nanos })
+}
+
+fn hash() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![Just(vec![]), vec(any::<u8>(), 32)]
+}
+
+fn address() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 20)
+}
+
+fn block_id() -> impl Strategy<Value = BlockId> {
+    (hash(), any::<u32>(), hash()).prop_map(|(hash, total, part_set_hash)| BlockId {
+        hash,
+        part_set_header: Some(PartSetHeader {
+            total,
+            hash: part_set_hash,
+        }),
+    })
+}
+
+prop_compose! {
+    fn header()(
+        block in any::<u64>(),
+        app in any::<u64>(),
+        chain_id in "[a-z0-9-]{1,50}",
+        height in 1..i64::MAX,
+        time in timestamp(),
+        last_block_id in option::of(block_id()),
+        hashes in vec(hash(), 8),
+        proposer_address in address(),
+    ) -> Header {
+        Header {
+            version: Some(Consensus { block, app }),
+            chain_id,
+            height,
+            time: Some(time),
+            last_block_id,
+            last_commit_hash: hashes[0].clone(),
+            data_hash: hashes[1].clone(),
+            validators_hash: hashes[2].clone(),
+            next_validators_hash: hashes[3].clone(),
+            consensus_hash: hashes[4].clone(),
+            app_hash: hashes[5].clone(),
+            last_results_hash: hashes[6].clone(),
+            evidence_hash: hashes[7].clone(),
+            proposer_address,
+        }
+    }
+}
+
+prop_compose! {
+    fn vote()(
+        r#type in prop_oneof![Just(SignedMsgType::Prevote), Just(SignedMsgType::Precommit)],
+        height in 1..i64::MAX,
+        round in 0..i32::MAX,
+        block_id in option::of(block_id()),
+        timestamp in option::of(timestamp()),
+        validator_address in address(),
+        validator_index in 0..i32::MAX,
+        signature in vec(any::<u8>(), 64),
+    ) -> Vote {
+        Vote {
+            r#type: r#type.into(),
+            height,
+            round,
+            block_id,
+            timestamp,
+            validator_address,
+            validator_index,
+            signature,
+        }
+    }
+}
+
+fn commit_sig() -> impl Strategy<Value = CommitSig> {
+    prop_oneof![
+        Just(CommitSig {
+            block_id_flag: BlockIdFlag::Absent.into(),
+            ..Default::default()
+        }),
+        (
+            prop_oneof![Just(BlockIdFlag::Commit), Just(BlockIdFlag::Nil)],
+            address(),
+            timestamp(),
+            vec(any::<u8>(), 64),
+        )
+            .prop_map(|(block_id_flag, validator_address, timestamp, signature)| {
+                CommitSig {
+                    block_id_flag: block_id_flag.into(),
+                    validator_address,
+                    timestamp: Some(timestamp),
+                    signature,
+                }
+            }),
+    ]
+}
+
+prop_compose! {
+    fn commit()(
+        height in 1..i64::MAX,
+        round in 0..i32::MAX,
+        block_id in option::of(block_id()),
+        signatures in vec(commit_sig(), 0..10),
+    ) -> Commit {
+        Commit { height, round, block_id, signatures }
+    }
+}
+
+fn validator() -> impl Strategy<Value = Validator> {
+    (
+        address(),
+        prop_oneof![
+            vec(any::<u8>(), 32).prop_map(Sum::Ed25519),
+            vec(any::<u8>(), 33).prop_map(Sum::Secp256k1),
+        ],
+        0..i64::MAX,
+        any::<i64>(),
+    )
+        .prop_map(
+            |(address, sum, voting_power, proposer_priority)| Validator {
+                address,
+                pub_key: Some(PublicKey { sum: Some(sum) }),
+                voting_power,
+                proposer_priority,
+            },
+        )
+}
+
+fn validator_set() -> impl Strategy<Value = ValidatorSet> {
+    (vec(validator(), 1..10), any::<prop::sample::Index>()).prop_map(|(validators, proposer)| {
+        let total_voting_power = validators
+            .iter()
+            .fold(0_i64, |acc, v| acc.saturating_add(v.voting_power));
+        ValidatorSet {
+            proposer: Some(proposer.get(&validators).clone()),
+            validators,
+            total_voting_power,
+        }
+    })
+}
+
+fn assert_round_trips<T>(value: T)
+where
+    T: Message + Default + PartialEq + Debug + Serialize + DeserializeOwned,
+{
+    let wire = value.encode_to_vec();
+    assert_eq!(T::decode(wire.as_slice()).unwrap(), value);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value);
+}
+
+proptest! {
+    #[test]
+    fn header_round_trips(header in header()) {
+        assert_round_trips(header);
+    }
+
+    #[test]
+    fn vote_round_trips(vote in vote()) {
+        assert_round_trips(vote);
+    }
+
+    #[test]
+    fn commit_round_trips(commit in commit()) {
+        assert_round_trips(commit);
+    }
+
+    #[test]
+    fn validator_set_round_trips(validator_set in validator_set()) {
+        assert_round_trips(validator_set);
+    }
+}